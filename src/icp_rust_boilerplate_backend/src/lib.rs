@@ -9,6 +9,38 @@ use std::{borrow::Cow, cell::RefCell};
 type Memory = VirtualMemory<DefaultMemoryImpl>;
 type IdCell = Cell<u64, Memory>;
 
+// Generate the `Storable`/`BoundedStorable` impls for a candid-encoded entity so
+// the `Encode!/Decode!` boilerplate lives in exactly one place.
+macro_rules! impl_candid_storable {
+    ($ty:ty, $max_size:expr) => {
+        impl Storable for $ty {
+            fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+                Cow::Owned(Encode!(self).unwrap())
+            }
+
+            fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+                Decode!(bytes.as_ref(), Self).unwrap()
+            }
+        }
+
+        impl BoundedStorable for $ty {
+            const MAX_SIZE: u32 = $max_size;
+            const IS_FIXED_SIZE: bool = false;
+        }
+    };
+}
+
+// Sales pipeline stage a customer sits in. New customers start as `Lead`.
+#[derive(candid::CandidType, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+enum LifecycleStatus {
+    #[default]
+    Lead,
+    Contacted,
+    Qualified,
+    Active,
+    Churned,
+}
+
 #[derive(candid::CandidType, Clone, Serialize, Deserialize, Default)]
 struct Customer {
     id: u64,
@@ -16,22 +48,14 @@ struct Customer {
     email: String,
     phone: String,
     created_at: u64,
+    // Optional so that `Customer` records stored before these fields existed
+    // still decode: candid treats absent `opt` fields as `None`, which we
+    // surface as the `Lead` default via `Option::unwrap_or_default`.
+    status: Option<LifecycleStatus>,
+    status_changed_at: Option<u64>,
 }
 
-impl Storable for Customer {
-    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
-        Cow::Owned(Encode!(self).unwrap())
-    }
-
-    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
-        Decode!(bytes.as_ref(), Self).unwrap()
-    }
-}
-
-impl BoundedStorable for Customer {
-    const MAX_SIZE: u32 = 1024;
-    const IS_FIXED_SIZE: bool = false;
-}
+impl_candid_storable!(Customer, 1024);
 
 // Use "Interaction" instead of "Message" to represent customer interactions
 #[derive(candid::CandidType, Clone, Serialize, Deserialize, Default)]
@@ -42,23 +66,99 @@ struct Interaction {
     content: String,
     created_at: u64,
     updated_at: Option<u64>,
+    // When set, this interaction is a reply to another interaction, forming a thread.
+    parent_interaction_id: Option<u64>,
 }
 
-impl Storable for Interaction {
+impl_candid_storable!(Interaction, 1024);
+
+// Entity kind a posting refers to, so customers and interactions sharing a term
+// occupy disjoint key ranges within that term.
+const POSTING_KIND_CUSTOMER: u8 = 0;
+const POSTING_KIND_INTERACTION: u8 = 1;
+// Upper bound on a single indexed term; tokens come from the bounded customer and
+// interaction fields (at most 1024 bytes, which lowercasing can grow by up to
+// half), so this comfortably covers the longest possible token.
+const MAX_TERM_LEN: u32 = 2048;
+
+// Key into the inverted index: one entry per (term, document) pair, each mapping
+// to that document's term frequency, instead of a single posting list value that
+// grows with every document containing the term. Keeping postings as separate
+// keys means a term shared by many documents can't outgrow a `BoundedStorable`
+// value and trap the insert. Encoded term-first (null terminated) so entries sort
+// and range-scan by term prefix; the trailing null stops a term from colliding
+// with a longer term that shares its prefix. `kind` then `doc_id` follow so a
+// single term's customer and interaction postings form contiguous sub-ranges.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq)]
+struct TermPostingKey {
+    term: String,
+    kind: u8,
+    doc_id: u64,
+}
+
+impl Storable for TermPostingKey {
     fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
-        Cow::Owned(Encode!(self).unwrap())
+        let mut bytes = Vec::with_capacity(self.term.len() + 10);
+        bytes.extend_from_slice(self.term.as_bytes());
+        bytes.push(0);
+        bytes.push(self.kind);
+        bytes.extend_from_slice(&self.doc_id.to_be_bytes());
+        Cow::Owned(bytes)
     }
 
     fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
-        Decode!(bytes.as_ref(), Self).unwrap()
+        let len = bytes.len();
+        let term = String::from_utf8(bytes[..len - 10].to_vec()).unwrap();
+        let kind = bytes[len - 9];
+        let mut doc_id = [0u8; 8];
+        doc_id.copy_from_slice(&bytes[len - 8..len]);
+        Self {
+            term,
+            kind,
+            doc_id: u64::from_be_bytes(doc_id),
+        }
     }
 }
 
-impl BoundedStorable for Interaction {
-    const MAX_SIZE: u32 = 1024;
+impl BoundedStorable for TermPostingKey {
+    const MAX_SIZE: u32 = MAX_TERM_LEN + 10;
     const IS_FIXED_SIZE: bool = false;
 }
 
+// Composite key for the per-customer interaction index. Encoded big-endian so
+// that the stable map orders entries by `customer_id` first, letting us range
+// scan every interaction of a customer by its id prefix.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq)]
+struct CustomerInteractionKey {
+    customer_id: u64,
+    interaction_id: u64,
+}
+
+impl Storable for CustomerInteractionKey {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        let mut bytes = Vec::with_capacity(16);
+        bytes.extend_from_slice(&self.customer_id.to_be_bytes());
+        bytes.extend_from_slice(&self.interaction_id.to_be_bytes());
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        let mut customer_id = [0u8; 8];
+        let mut interaction_id = [0u8; 8];
+        customer_id.copy_from_slice(&bytes[0..8]);
+        interaction_id.copy_from_slice(&bytes[8..16]);
+        Self {
+            customer_id: u64::from_be_bytes(customer_id),
+            interaction_id: u64::from_be_bytes(interaction_id),
+        }
+    }
+}
+
+impl BoundedStorable for CustomerInteractionKey {
+    const MAX_SIZE: u32 = 16;
+    const IS_FIXED_SIZE: bool = true;
+}
+
 thread_local! {
     static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> = RefCell::new(
         MemoryManager::init(DefaultMemoryImpl::default())
@@ -78,6 +178,20 @@ thread_local! {
         RefCell::new(StableBTreeMap::init(
             MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(2)))
     ));
+
+    // Inverted index: (term, kind, doc id) -> term frequency. Kept consistent
+    // with CUSTOMER_STORAGE/INTERACTION_STORAGE on every mutation.
+    static TERM_INDEX: RefCell<StableBTreeMap<TermPostingKey, u32, Memory>> =
+        RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(3)))
+    ));
+
+    // Secondary index: (customer_id, interaction_id) -> (). Lets us enumerate a
+    // customer's interactions without scanning the whole INTERACTION_STORAGE.
+    static CUSTOMER_INTERACTION_INDEX: RefCell<StableBTreeMap<CustomerInteractionKey, (), Memory>> =
+        RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(4)))
+    ));
 }
 
 #[derive(candid::CandidType, Serialize, Deserialize, Default)]
@@ -85,6 +199,108 @@ struct InteractionPayload {
     customer_id: u64,
     interaction_type: String,
     content: String,
+    parent_interaction_id: Option<u64>,
+}
+
+// Tokenize free text into normalized terms: lowercased, split on any
+// non-alphanumeric character, with empties dropped.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+// Count term frequencies across the concatenation of a document's indexed fields.
+fn term_frequencies(fields: &[&str]) -> std::collections::HashMap<String, u32> {
+    let mut counts = std::collections::HashMap::new();
+    for field in fields {
+        for term in tokenize(field) {
+            *counts.entry(term).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+// Add/remove a customer's terms from the inverted index.
+fn index_customer(customer: &Customer) {
+    let freqs = term_frequencies(&[&customer.name, &customer.email, &customer.phone]);
+    TERM_INDEX.with(|index| {
+        let mut index = index.borrow_mut();
+        for (term, tf) in freqs {
+            index.insert(
+                TermPostingKey {
+                    term,
+                    kind: POSTING_KIND_CUSTOMER,
+                    doc_id: customer.id,
+                },
+                tf,
+            );
+        }
+    });
+}
+
+fn unindex_customer(customer: &Customer) {
+    let terms: Vec<String> = term_frequencies(&[&customer.name, &customer.email, &customer.phone])
+        .into_keys()
+        .collect();
+    TERM_INDEX.with(|index| {
+        let mut index = index.borrow_mut();
+        for term in terms {
+            index.remove(&TermPostingKey {
+                term,
+                kind: POSTING_KIND_CUSTOMER,
+                doc_id: customer.id,
+            });
+        }
+    });
+}
+
+// Add/remove an interaction's terms from the inverted index.
+fn index_interaction(interaction: &Interaction) {
+    let freqs = term_frequencies(&[&interaction.interaction_type, &interaction.content]);
+    TERM_INDEX.with(|index| {
+        let mut index = index.borrow_mut();
+        for (term, tf) in freqs {
+            index.insert(
+                TermPostingKey {
+                    term,
+                    kind: POSTING_KIND_INTERACTION,
+                    doc_id: interaction.id,
+                },
+                tf,
+            );
+        }
+    });
+}
+
+fn unindex_interaction(interaction: &Interaction) {
+    let terms: Vec<String> =
+        term_frequencies(&[&interaction.interaction_type, &interaction.content])
+            .into_keys()
+            .collect();
+    TERM_INDEX.with(|index| {
+        let mut index = index.borrow_mut();
+        for term in terms {
+            index.remove(&TermPostingKey {
+                term,
+                kind: POSTING_KIND_INTERACTION,
+                doc_id: interaction.id,
+            });
+        }
+    });
+}
+
+// Clamp a pagination window to the available range so that a page past the end
+// yields an empty slice rather than panicking on an out-of-bounds index.
+fn paginate<T: Clone>(items: &[T], page_size: u64, page_number: u64) -> Vec<T> {
+    let total_items = items.len();
+    let start_index = (page_number.saturating_sub(1) as usize).saturating_mul(page_size as usize);
+    if start_index >= total_items {
+        return Vec::new();
+    }
+    let end_index = start_index.saturating_add(page_size as usize).min(total_items);
+    items[start_index..end_index].to_vec()
 }
 
 // Helper method to get an interaction by id. Used in get_interaction/update_interaction
@@ -102,6 +318,115 @@ fn get_interaction(id: u64) -> Result<Interaction, Error> {
     }
 }
 
+// Gather the ids of every interaction belonging to a customer via a prefix
+// range scan on the secondary index.
+fn customer_interaction_ids(customer_id: u64) -> Vec<u64> {
+    let start = CustomerInteractionKey {
+        customer_id,
+        interaction_id: u64::MIN,
+    };
+    let end = CustomerInteractionKey {
+        customer_id,
+        interaction_id: u64::MAX,
+    };
+    CUSTOMER_INTERACTION_INDEX.with(|index| {
+        index.borrow().range(start..=end)
+            .into_iter()
+            .map(|(key, _)| key.interaction_id)
+            .collect()
+    })
+}
+
+#[ic_cdk::query]
+fn get_customer_interactions(
+    customer_id: u64,
+    interaction_type: Option<String>,
+    from_ts: Option<u64>,
+    to_ts: Option<u64>,
+    page_size: u64,
+    page_number: u64,
+) -> SearchResult<Interaction> {
+    let mut interactions: Vec<Interaction> = customer_interaction_ids(customer_id)
+        .into_iter()
+        .filter_map(|id| _get_interaction(&id))
+        .filter(|interaction| {
+            let type_match = interaction_type
+                .as_ref()
+                .map_or(true, |t| &interaction.interaction_type == t);
+            let from_match = from_ts.map_or(true, |ts| interaction.created_at >= ts);
+            let to_match = to_ts.map_or(true, |ts| interaction.created_at <= ts);
+            type_match && from_match && to_match
+        })
+        .collect();
+
+    // Newest first.
+    interactions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    let total_items = interactions.len();
+    let items = paginate(&interactions, page_size, page_number);
+
+    SearchResult { total_items, items }
+}
+
+#[ic_cdk::query]
+fn get_interaction_thread(root_id: u64) -> Result<Vec<Interaction>, Error> {
+    let root = _get_interaction(&root_id).ok_or_else(|| Error::NotFound {
+        msg: format!("an interaction with id={} not found", root_id),
+    })?;
+
+    // Build a parent -> children map from the root's customer interactions,
+    // then walk it breadth-first. Replies filed under a different customer
+    // than the root are not part of the returned thread.
+    let mut children: std::collections::HashMap<u64, Vec<Interaction>> =
+        std::collections::HashMap::new();
+    for id in customer_interaction_ids(root.customer_id) {
+        if let Some(interaction) = _get_interaction(&id) {
+            if let Some(parent_id) = interaction.parent_interaction_id {
+                children.entry(parent_id).or_default().push(interaction);
+            }
+        }
+    }
+
+    let mut thread = vec![root.clone()];
+    let mut queue = std::collections::VecDeque::new();
+    // Track visited ids so a parent cycle (A -> B -> A, both legal updates)
+    // can't loop the walk forever and trap on the instruction limit.
+    let mut visited = std::collections::HashSet::new();
+    visited.insert(root_id);
+    queue.push_back(root_id);
+    while let Some(current) = queue.pop_front() {
+        if let Some(kids) = children.get(&current) {
+            for kid in kids {
+                if visited.insert(kid.id) {
+                    queue.push_back(kid.id);
+                    thread.push(kid.clone());
+                }
+            }
+        }
+    }
+
+    thread.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(thread)
+}
+
+// Validate an optional reply reference: the parent must exist and an
+// interaction cannot be its own parent.
+fn validate_parent(parent: Option<u64>, self_id: Option<u64>) -> Result<(), Error> {
+    if let Some(parent_id) = parent {
+        if Some(parent_id) == self_id {
+            return Err(Error::InvalidInput {
+                msg: "an interaction cannot be its own parent".to_string(),
+            });
+        }
+        if _get_interaction(&parent_id).is_none() {
+            return Err(Error::NotFound {
+                msg: format!("parent interaction with id={} not found", parent_id),
+            });
+        }
+    }
+    Ok(())
+}
+
 // Validate interaction payload
 fn is_valid_interaction_payload(payload: &InteractionPayload) -> bool {
     // Add your validation logic here
@@ -119,6 +444,13 @@ fn add_interaction(payload: InteractionPayload) -> Result<Interaction, Error> {
         });
     }
 
+    // Referential integrity: an interaction must belong to a real customer.
+    if _get_customer(&payload.customer_id).is_none() {
+        return Err(Error::NotFound {
+            msg: format!("a customer with id={} not found", payload.customer_id),
+        });
+    }
+
     let id = CUSTOMER_ID_COUNTER
         .with(|counter| {
             let current_value = *counter.borrow().get();
@@ -126,6 +458,9 @@ fn add_interaction(payload: InteractionPayload) -> Result<Interaction, Error> {
         })
         .expect("cannot increment interaction id counter");
 
+    // Reject replies that reference a missing (or self) parent.
+    validate_parent(payload.parent_interaction_id, Some(id))?;
+
     let interaction = Interaction {
         id,
         customer_id: payload.customer_id,
@@ -133,9 +468,11 @@ fn add_interaction(payload: InteractionPayload) -> Result<Interaction, Error> {
         content: payload.content,
         created_at: time(),
         updated_at: None,
+        parent_interaction_id: payload.parent_interaction_id,
     };
 
     do_insert_interaction(&interaction);
+    index_interaction(&interaction);
     Ok(interaction)
 }
 
@@ -149,12 +486,23 @@ fn update_interaction(id: u64, payload: InteractionPayload) -> Result<Interactio
         });
     }
 
-    match INTERACTION_STORAGE.with(|service| service.borrow_mut().get(&id)) {
+    // Referential integrity: an interaction must belong to a real customer.
+    if _get_customer(&payload.customer_id).is_none() {
+        return Err(Error::NotFound {
+            msg: format!("a customer with id={} not found", payload.customer_id),
+        });
+    }
+
+    match INTERACTION_STORAGE.with(|service| service.borrow().get(&id)) {
         Some(mut interaction) => {
+            validate_parent(payload.parent_interaction_id, Some(interaction.id))?;
+            unindex_interaction(&interaction);
             interaction.interaction_type = payload.interaction_type;
             interaction.content = payload.content;
+            interaction.parent_interaction_id = payload.parent_interaction_id;
             interaction.updated_at = Some(time());
             do_insert_interaction(&interaction);
+            index_interaction(&interaction);
             Ok(interaction.clone())
         }
         None => Err(Error::NotFound {
@@ -168,7 +516,17 @@ fn update_interaction(id: u64, payload: InteractionPayload) -> Result<Interactio
 
 // Update the function names and variables to reflect the CRM logic
 fn do_insert_interaction(interaction: &Interaction) {
-    INTERACTION_STORAGE.with(|service| service.borrow_mut().insert(interaction.id, interaction.clone()));
+    INTERACTION_STORAGE
+        .with(|service| service.borrow_mut().insert(interaction.id, interaction.clone()));
+    CUSTOMER_INTERACTION_INDEX.with(|index| {
+        index.borrow_mut().insert(
+            CustomerInteractionKey {
+                customer_id: interaction.customer_id,
+                interaction_id: interaction.id,
+            },
+            (),
+        )
+    });
 }
 
 // Helper method to get a customer by id. Used in get_customer/update_customer
@@ -222,21 +580,34 @@ fn add_customer(name: String, email: String, phone: String) -> Result<Customer,
         email,
         phone,
         created_at: time(),
+        status: Some(LifecycleStatus::Lead),
+        status_changed_at: Some(time()),
     };
 
     do_insert_customer(&customer);
+    index_customer(&customer);
     Ok(customer)
 }
 
 // Update the function names and variables to reflect the CRM logic
 fn do_insert_customer(customer: &Customer) {
-    CUSTOMER_STORAGE.with(|service| service.borrow_mut().insert(customer.id, customer.clone()));
+    CUSTOMER_STORAGE
+        .with(|service| service.borrow_mut().insert(customer.id, customer.clone()));
 }
 
 #[ic_cdk::update]
 fn delete_interaction(id: u64) -> Result<Interaction, Error> {
     match INTERACTION_STORAGE.with(|service| service.borrow_mut().remove(&id)) {
-        Some(interaction) => Ok(interaction),
+        Some(interaction) => {
+            unindex_interaction(&interaction);
+            CUSTOMER_INTERACTION_INDEX.with(|index| {
+                index.borrow_mut().remove(&CustomerInteractionKey {
+                    customer_id: interaction.customer_id,
+                    interaction_id: interaction.id,
+                })
+            });
+            Ok(interaction)
+        }
         None => Err(Error::NotFound {
             msg: format!(
                 "couldn't delete an interaction with id={}. Interaction not found",
@@ -256,11 +627,14 @@ fn update_customer(id: u64, name: String, email: String, phone: String) -> Resul
         });
     }
 
-    match CUSTOMER_STORAGE.with(|service| service.borrow_mut().get(&id)) {
+    match CUSTOMER_STORAGE.with(|service| service.borrow().get(&id)) {
         Some(mut customer) => {
+            unindex_customer(&customer);
             customer.name = name;
             customer.email = email;
             customer.phone = phone;
+            do_insert_customer(&customer);
+            index_customer(&customer);
             Ok(customer.clone())
         }
         None => Err(Error::NotFound {
@@ -272,10 +646,35 @@ fn update_customer(id: u64, name: String, email: String, phone: String) -> Resul
     }
 }
 
+/// Delete a customer. When `cascade` is true, every interaction belonging to
+/// the customer is deleted as well; when false, the call is rejected with
+/// `Error::InvalidInput` if the customer still has interactions, to avoid
+/// leaving them dangling.
 #[ic_cdk::update]
-fn delete_customer(id: u64) -> Result<Customer, Error> {
+fn delete_customer(id: u64, cascade: bool) -> Result<Customer, Error> {
+    let interaction_ids = customer_interaction_ids(id);
+    if !interaction_ids.is_empty() {
+        if !cascade {
+            return Err(Error::InvalidInput {
+                msg: format!(
+                    "customer id={} has {} interaction(s); pass cascade=true to delete them",
+                    id,
+                    interaction_ids.len()
+                ),
+            });
+        }
+        for interaction_id in interaction_ids {
+            // Reuses the single-interaction delete so the inverted index and the
+            // per-customer secondary index stay consistent.
+            let _ = delete_interaction(interaction_id);
+        }
+    }
+
     match CUSTOMER_STORAGE.with(|service| service.borrow_mut().remove(&id)) {
-        Some(customer) => Ok(customer),
+        Some(customer) => {
+            unindex_customer(&customer);
+            Ok(customer)
+        }
         None => Err(Error::NotFound {
             msg: format!(
                 "couldn't delete a customer with id={}. Customer not found",
@@ -285,6 +684,65 @@ fn delete_customer(id: u64) -> Result<Customer, Error> {
     }
 }
 
+// Whether `new` is a legal next stage from `current`. Customers advance one
+// stage at a time (Lead -> Contacted -> Qualified -> Active); `Churned` is
+// reachable from any engaged stage but a record can never skip a stage.
+fn is_valid_status_transition(current: LifecycleStatus, new: LifecycleStatus) -> bool {
+    use LifecycleStatus::*;
+    matches!(
+        (current, new),
+        (Lead, Contacted)
+            | (Contacted, Qualified)
+            | (Qualified, Active)
+            | (Contacted, Churned)
+            | (Qualified, Churned)
+            | (Active, Churned)
+    )
+}
+
+#[ic_cdk::update]
+fn set_customer_status(id: u64, new_status: LifecycleStatus) -> Result<Customer, Error> {
+    match _get_customer(&id) {
+        Some(mut customer) => {
+            if !is_valid_status_transition(customer.status.unwrap_or_default(), new_status) {
+                return Err(Error::InvalidInput {
+                    msg: format!(
+                        "illegal lifecycle transition for customer id={}",
+                        id
+                    ),
+                });
+            }
+            customer.status = Some(new_status);
+            customer.status_changed_at = Some(time());
+            do_insert_customer(&customer);
+            Ok(customer)
+        }
+        None => Err(Error::NotFound {
+            msg: format!("a customer with id={} not found", id),
+        }),
+    }
+}
+
+#[ic_cdk::query]
+fn get_pipeline(
+    status: LifecycleStatus,
+    page_size: u64,
+    page_number: u64,
+) -> SearchResult<Customer> {
+    let customers: Vec<Customer> = CUSTOMER_STORAGE.with(|service| {
+        service.borrow().iter()
+            .into_iter()
+            .filter(|(_, customer)| customer.status.unwrap_or_default() == status)
+            .map(|(_, customer)| customer)
+            .collect()
+    });
+
+    let total_items = customers.len();
+    let items = paginate(&customers, page_size, page_number);
+
+    SearchResult { total_items, items }
+}
+
 #[derive(candid::CandidType, Deserialize, Serialize)]
 enum Error {
     NotFound { msg: String },
@@ -299,27 +757,22 @@ fn search_customers(
     page_size: u64,
     page_number: u64,
 ) -> SearchResult<Customer> {
-    let all_customers: Vec<Customer> = CUSTOMER_STORAGE
-        .with(|service| {
-            service
-                .borrow()
-                .iter()
-                .filter(|(_, customer)| {
-                    let name_match = name.as_ref().map_or(true, |n| &customer.name == n);
-                    let email_match = email.as_ref().map_or(true, |e| &customer.email == e);
-                    let phone_match = phone.as_ref().map_or(true, |p| &customer.phone == p);
-
-                    name_match && email_match && phone_match
-                })
-                .map(|(_, customer)| customer.clone())
-                .collect()
-        });
+    let all_customers: Vec<Customer> = CUSTOMER_STORAGE.with(|service| {
+        service.borrow().iter()
+            .into_iter()
+            .filter(|(_, customer)| {
+                let name_match = name.as_ref().map_or(true, |n| &customer.name == n);
+                let email_match = email.as_ref().map_or(true, |e| &customer.email == e);
+                let phone_match = phone.as_ref().map_or(true, |p| &customer.phone == p);
+
+                name_match && email_match && phone_match
+            })
+            .map(|(_, customer)| customer)
+            .collect()
+    });
 
     let total_items = all_customers.len();
-    let start_index = (page_number - 1) as usize * page_size as usize;
-    let end_index = (start_index + page_size as usize).min(total_items);
-
-    let paginated_customers = all_customers[start_index..end_index].to_vec();
+    let paginated_customers = paginate(&all_customers, page_size, page_number);
 
     SearchResult {
         total_items,
@@ -327,6 +780,78 @@ fn search_customers(
     }
 }
 
+// A customer together with its relevance score for a full-text query.
+#[derive(candid::CandidType, Clone, Serialize, Deserialize)]
+struct ScoredCustomer {
+    customer: Customer,
+    // Number of distinct query terms matched; primary ranking key.
+    matched_terms: u32,
+    // Summed term frequency over the matched query terms; tie-breaker.
+    score: u32,
+}
+
+#[ic_cdk::query]
+fn full_text_search(
+    query: String,
+    page_size: u64,
+    page_number: u64,
+) -> SearchResult<ScoredCustomer> {
+    // Dedup the tokenized query so a repeated term (e.g. "acme acme corp")
+    // can't inflate `matched_terms` and corrupt the distinct-term ranking.
+    let query_terms: Vec<String> = tokenize(&query)
+        .into_iter()
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    // Accumulate per-customer (distinct matched terms, summed tf) by range
+    // scanning each query term's customer postings.
+    let mut scores: std::collections::HashMap<u64, (u32, u32)> = std::collections::HashMap::new();
+    TERM_INDEX.with(|index| {
+        let index = index.borrow();
+        for term in &query_terms {
+            let start = TermPostingKey {
+                term: term.clone(),
+                kind: POSTING_KIND_CUSTOMER,
+                doc_id: u64::MIN,
+            };
+            let end = TermPostingKey {
+                term: term.clone(),
+                kind: POSTING_KIND_CUSTOMER,
+                doc_id: u64::MAX,
+            };
+            for (key, tf) in index.range(start..=end) {
+                let entry = scores.entry(key.doc_id).or_insert((0, 0));
+                entry.0 += 1;
+                entry.1 += tf;
+            }
+        }
+    });
+
+    // Resolve ids to records and rank: more distinct terms first, then higher tf.
+    let mut scored: Vec<ScoredCustomer> = scores
+        .into_iter()
+        .filter_map(|(customer_id, (matched_terms, score))| {
+            _get_customer(&customer_id).map(|customer| ScoredCustomer {
+                customer,
+                matched_terms,
+                score,
+            })
+        })
+        .collect();
+    scored.sort_by(|a, b| {
+        b.matched_terms
+            .cmp(&a.matched_terms)
+            .then(b.score.cmp(&a.score))
+            .then(a.customer.id.cmp(&b.customer.id))
+    });
+
+    let total_items = scored.len();
+    let items = paginate(&scored, page_size, page_number);
+
+    SearchResult { total_items, items }
+}
+
 // Define a SearchResult struct to hold pagination information
 #[derive(candid::CandidType, Serialize, Deserialize)]
 struct SearchResult<T> {